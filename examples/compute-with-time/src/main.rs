@@ -15,17 +15,58 @@ use bevy::{
         renderer::{
             RenderContext, RenderDevice, RenderQueue,
         },
+        texture::GpuImage,
         RenderApp, RenderStage,
     },
     window::WindowDescriptor,
 };
 use bevy_shader_utils::ShaderUtilsPlugin;
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, Sender},
+        Arc,
+    },
+};
+
+/// `copy_texture_to_buffer` requires each row of the destination buffer to be
+/// padded out to a multiple of this many bytes.
+const COPY_BYTES_PER_ROW_ALIGNMENT: u32 = 256;
 
-const SIZE: (u32, u32) = (1280, 720);
-// const SIZE: (u32, u32) = (3840, 2160);
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded_bytes_per_row = width * 4;
+    let align = COPY_BYTES_PER_ROW_ALIGNMENT;
+    (unpadded_bytes_per_row + align - 1) / align * align
+}
+
+fn ceil_div(value: u32, divisor: u32) -> u32 {
+    (value + divisor - 1) / divisor
+}
+
+/// Must match the literal in `@workgroup_size(...)` in `flow.wgsl`, since
+/// WGSL can't take it as a runtime value. Not part of `GameOfLifeConfig`
+/// since, unlike grid size, there's no way to change it without also
+/// editing the shader.
 const WORKGROUP_SIZE: u32 = 8;
 
+/// The runtime-configurable grid size, created at startup and extracted
+/// into the render world.
+#[derive(Resource, Clone, Copy, ExtractResource)]
+struct GameOfLifeConfig {
+    width: u32,
+    height: u32,
+}
+
+impl Default for GameOfLifeConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+        }
+    }
+}
+
 fn main() {
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
@@ -35,43 +76,60 @@ fn main() {
         }))
         .add_plugin(ShaderUtilsPlugin)
         .add_plugin(GameOfLifeComputePlugin)
+        .init_resource::<GameOfLifeConfig>()
+        .init_resource::<GameOfLifeRules>()
+        .init_resource::<GameOfLifeReadbackRequest>()
         .add_startup_system(setup)
+        .add_system(update_sprite_texture)
+        .add_system(cycle_rules)
+        .add_system(request_readback)
+        .add_system(receive_readback)
         .run();
 }
 
 fn setup(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
+    config: Res<GameOfLifeConfig>,
 ) {
-    let mut image = Image::new_fill(
-        Extent3d {
-            width: SIZE.0,
-            height: SIZE.1,
-            depth_or_array_layers: 1,
-        },
-        TextureDimension::D2,
-        &[0, 0, 0, 255],
-        TextureFormat::Rgba8Unorm,
-    );
-    image.texture_descriptor.usage = TextureUsages::COPY_DST
-        | TextureUsages::STORAGE_BINDING
-        | TextureUsages::TEXTURE_BINDING;
-    let image = images.add(image);
+    let mut make_image = || {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8Unorm,
+        );
+        image.texture_descriptor.usage = TextureUsages::COPY_SRC
+            | TextureUsages::COPY_DST
+            | TextureUsages::STORAGE_BINDING
+            | TextureUsages::TEXTURE_BINDING;
+        images.add(image)
+    };
+
+    let texture_a = make_image();
+    let texture_b = make_image();
 
     commands.spawn(SpriteBundle {
         sprite: Sprite {
             custom_size: Some(Vec2::new(
-                SIZE.0 as f32,
-                SIZE.1 as f32,
+                config.width as f32,
+                config.height as f32,
             )),
             ..default()
         },
-        texture: image.clone(),
+        texture: texture_a.clone(),
         ..default()
     });
     commands.spawn(Camera2dBundle::default());
 
-    commands.insert_resource(GameOfLifeImage(image));
+    commands.insert_resource(GameOfLifeImages {
+        texture_a,
+        texture_b,
+    });
 }
 
 pub struct GameOfLifeComputePlugin;
@@ -90,26 +148,80 @@ impl Plugin for GameOfLifeComputePlugin {
                 mapped_at_creation: false,
             },
         );
+        let dimensions_buffer = render_device.create_buffer(
+            &BufferDescriptor {
+                label: Some("dimensions uniform buffer"),
+                size: std::mem::size_of::<[u32; 2]>() as u64,
+                usage: BufferUsages::UNIFORM
+                    | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+
+        // Shared with the render world so `GameOfLifeNode::run` can flip it
+        // every frame and the main world can read the result back without
+        // waiting on an extract round-trip.
+        let front_buffer = GameOfLifeFrontBuffer(Arc::new(AtomicBool::new(false)));
+        app.insert_resource(front_buffer.clone());
+
+        // Snapshots decoded on the render device are sent back to the main
+        // world over this channel rather than an extract, since the readback
+        // only completes a frame or more after it is requested.
+        let (readback_sender, readback_receiver) = std::sync::mpsc::channel();
+        app.insert_resource(GameOfLifeReadbackReceiver(
+            readback_receiver,
+        ));
+
         app.add_plugin(ExtractResourcePlugin::<
-            GameOfLifeImage,
+            GameOfLifeImages,
         >::default())
+            .add_plugin(ExtractResourcePlugin::<
+                GameOfLifeConfig,
+            >::default())
+            .add_plugin(ExtractResourcePlugin::<
+                GameOfLifeRules,
+            >::default())
+            .add_plugin(ExtractResourcePlugin::<
+                GameOfLifeReadbackRequest,
+            >::default())
             .add_plugin(ExtractResourcePlugin::<
                 ExtractedTime,
             >::default());
         let render_app = app.sub_app_mut(RenderApp);
         render_app
             .init_resource::<GameOfLifePipeline>()
+            .init_resource::<SpecializedComputePipelines<GameOfLifePipeline>>()
+            .init_resource::<GameOfLifePendingReadback>()
+            .insert_resource(front_buffer)
+            .insert_resource(GameOfLifeReadbackSender(
+                readback_sender,
+            ))
             .insert_resource(TimeMeta {
                 buffer,
                 bind_group: None,
             })
+            .insert_resource(DimensionsMeta {
+                buffer: dimensions_buffer,
+            })
             .add_system_to_stage(
                 RenderStage::Queue,
                 queue_bind_group,
             )
+            .add_system_to_stage(
+                RenderStage::Queue,
+                queue_pipeline,
+            )
             .add_system_to_stage(
                 RenderStage::Prepare,
                 prepare_time,
+            )
+            .add_system_to_stage(
+                RenderStage::Prepare,
+                prepare_dimensions,
+            )
+            .add_system_to_stage(
+                RenderStage::Cleanup,
+                map_and_send_readback,
             );
 
         let mut render_graph =
@@ -127,52 +239,192 @@ impl Plugin for GameOfLifeComputePlugin {
     }
 }
 
-// Resource is opt-in in main branch
-#[derive(Resource, Clone, Deref, ExtractResource)]
-struct GameOfLifeImage(Handle<Image>);
+/// The two textures that are ping-ponged between every frame: whichever one
+/// the compute pass just wrote into becomes the "front" buffer that gets
+/// displayed, while the other becomes the "back" buffer the next dispatch
+/// writes into, reading its neighbor cells from the front buffer.
+#[derive(Resource, Clone, ExtractResource)]
+struct GameOfLifeImages {
+    texture_a: Handle<Image>,
+    texture_b: Handle<Image>,
+}
+
+/// Tracks which of `texture_a` / `texture_b` is currently the front buffer:
+/// `false` means `texture_a`, `true` means `texture_b`. The same
+/// `Arc<AtomicBool>` is held by both sub-apps, so [`GameOfLifeNode::run`] can
+/// flip it the instant a dispatch finishes and [`update_sprite_texture`] sees
+/// the new value on the very next frame.
+#[derive(Resource, Clone)]
+struct GameOfLifeFrontBuffer(Arc<AtomicBool>);
+
+/// Updates the displayed sprite to whichever texture the render node last
+/// wrote to, so the main world always shows the latest completed generation.
+fn update_sprite_texture(
+    images: Res<GameOfLifeImages>,
+    front_buffer: Res<GameOfLifeFrontBuffer>,
+    mut sprite_query: Query<&mut Handle<Image>, With<Sprite>>,
+) {
+    let front = if front_buffer.0.load(Ordering::Relaxed) {
+        &images.texture_b
+    } else {
+        &images.texture_a
+    };
+    for mut texture in sprite_query.iter_mut() {
+        if *texture != *front {
+            *texture = front.clone();
+        }
+    }
+}
+
+/// Set for a single frame whenever the user wants to pull the current
+/// generation back to the CPU, e.g. to snapshot or export it. The render
+/// world only pays for a `copy_texture_to_buffer` + map on frames where this
+/// is `true`.
+#[derive(Resource, Clone, Copy, Default, ExtractResource)]
+struct GameOfLifeReadbackRequest(bool);
+
+/// Press `C` to capture the current generation to the CPU.
+fn request_readback(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut readback_request: ResMut<GameOfLifeReadbackRequest>,
+) {
+    readback_request.0 =
+        keyboard_input.just_pressed(KeyCode::C);
+}
+
+/// The main-world side of the readback channel; receives decoded cell grids
+/// produced by [`map_and_send_readback`] in the render world.
+#[derive(Resource)]
+struct GameOfLifeReadbackReceiver(Receiver<Vec<u8>>);
+
+/// Drains completed snapshots and hands them off for inspection/export. This
+/// example just logs how many bytes came back; a real user would decode the
+/// RGBA8 buffer into an image or scan it for stable/oscillating patterns.
+fn receive_readback(
+    readback_receiver: Res<GameOfLifeReadbackReceiver>,
+) {
+    for snapshot in readback_receiver.0.try_iter() {
+        info!(
+            "Game of Life snapshot ready: {} bytes",
+            snapshot.len()
+        );
+    }
+}
+
+/// Birth/survival neighbor-count masks for a Life-like cellular automaton,
+/// e.g. B3/S23 (Conway) or B36/S23 (HighLife). Bit `n` of `birth` set means a
+/// dead cell with `n` live neighbors is born; bit `n` of `survival` set means
+/// a live cell with `n` live neighbors survives.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Hash, ExtractResource)]
+struct GameOfLifeRules {
+    birth: u32,
+    survival: u32,
+}
+
+impl GameOfLifeRules {
+    const CONWAY: Self = Self {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+    };
+    const HIGH_LIFE: Self = Self {
+        birth: (1 << 3) | (1 << 6),
+        survival: (1 << 2) | (1 << 3),
+    };
+
+    fn next(self) -> Self {
+        if self == Self::CONWAY {
+            Self::HIGH_LIFE
+        } else {
+            Self::CONWAY
+        }
+    }
+}
+
+impl Default for GameOfLifeRules {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+/// Press `R` to switch between Life-like rule sets and watch the pattern
+/// evolve differently. Changing the resource causes the render world to
+/// specialize (and cache) a distinct compute pipeline variant for the new
+/// rules via `queue_pipeline`.
+fn cycle_rules(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut rules: ResMut<GameOfLifeRules>,
+) {
+    if keyboard_input.just_pressed(KeyCode::R) {
+        *rules = rules.next();
+    }
+}
 
 #[derive(Resource)]
-struct GameOfLifeImageBindGroup(BindGroup);
+struct GameOfLifeImageBindGroups {
+    /// Reads `texture_a`, writes `texture_b`.
+    a_to_b: BindGroup,
+    /// Reads `texture_b`, writes `texture_a`.
+    b_to_a: BindGroup,
+}
 
 fn queue_bind_group(
     mut commands: Commands,
     pipeline: Res<GameOfLifePipeline>,
     gpu_images: Res<RenderAssets<Image>>,
-    game_of_life_image: Res<GameOfLifeImage>,
+    game_of_life_images: Res<GameOfLifeImages>,
     render_device: Res<RenderDevice>,
     time_meta: ResMut<TimeMeta>,
+    dimensions_meta: ResMut<DimensionsMeta>,
 ) {
-    let view = &gpu_images[&game_of_life_image.0];
-    let bind_group = render_device.create_bind_group(
-        &BindGroupDescriptor {
-            label: None,
-            layout: &pipeline.texture_bind_group_layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::TextureView(
-                        &view.texture_view,
-                    ),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: time_meta
-                        .buffer
-                        .as_entire_binding(),
-                },
-            ],
-        },
-    );
-    commands.insert_resource(GameOfLifeImageBindGroup(
-        bind_group,
-    ));
+    let view_a = &gpu_images[&game_of_life_images.texture_a];
+    let view_b = &gpu_images[&game_of_life_images.texture_b];
+
+    let make_bind_group = |input: &GpuImage, output: &GpuImage| {
+        render_device.create_bind_group(
+            &BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.texture_bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(
+                            &input.texture_view,
+                        ),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::TextureView(
+                            &output.texture_view,
+                        ),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: time_meta
+                            .buffer
+                            .as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: dimensions_meta
+                            .buffer
+                            .as_entire_binding(),
+                    },
+                ],
+            },
+        )
+    };
+
+    commands.insert_resource(GameOfLifeImageBindGroups {
+        a_to_b: make_bind_group(view_a, view_b),
+        b_to_a: make_bind_group(view_b, view_a),
+    });
 }
 
 #[derive(Resource)]
 pub struct GameOfLifePipeline {
     texture_bind_group_layout: BindGroupLayout,
+    shader: Handle<Shader>,
     init_pipeline: CachedComputePipelineId,
-    update_pipeline: CachedComputePipelineId,
 }
 
 impl FromWorld for GameOfLifePipeline {
@@ -182,31 +434,58 @@ impl FromWorld for GameOfLifePipeline {
                 .resource::<RenderDevice>()
                 .create_bind_group_layout(&BindGroupLayoutDescriptor {
                     label: None,
-                    entries: &[BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: ShaderStages::COMPUTE,
-                        ty: BindingType::StorageTexture {
-                            access: StorageTextureAccess::ReadWrite,
-                            format: TextureFormat::Rgba8Unorm,
-                            view_dimension: TextureViewDimension::D2,
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::StorageTexture {
+                                access: StorageTextureAccess::ReadOnly,
+                                format: TextureFormat::Rgba8Unorm,
+                                view_dimension: TextureViewDimension::D2,
+                            },
+                            count: None,
                         },
-                        count: None,
-                    },BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: ShaderStages::COMPUTE,
-                        ty: BindingType::Buffer {
-                            ty: BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: BufferSize::new(std::mem::size_of::<f32>() as u64),
+                        BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::StorageTexture {
+                                access: StorageTextureAccess::WriteOnly,
+                                format: TextureFormat::Rgba8Unorm,
+                                view_dimension: TextureViewDimension::D2,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: BufferSize::new(std::mem::size_of::<f32>() as u64),
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 3,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: BufferSize::new(std::mem::size_of::<[u32; 2]>() as u64),
+                            },
+                            count: None,
                         },
-                        count: None,
-                    }],
+                    ],
                 });
         let shader = world
             .resource::<AssetServer>()
             .load("shaders/flow.wgsl");
         let mut pipeline_cache =
             world.resource_mut::<PipelineCache>();
+        // `init` never reads the rule masks, but they're module-scope consts
+        // in the shader source, so they still have to be defined for `init`
+        // to preprocess; the actual values are irrelevant here.
+        let default_rules = GameOfLifeRules::default();
         let init_pipeline = pipeline_cache
             .queue_compute_pipeline(
                 ComputePipelineDescriptor {
@@ -215,31 +494,68 @@ impl FromWorld for GameOfLifePipeline {
                         texture_bind_group_layout.clone(),
                     ]),
                     shader: shader.clone(),
-                    shader_defs: vec![],
+                    shader_defs: vec![
+                        ShaderDefVal::UInt(
+                            "BIRTH_MASK".into(),
+                            default_rules.birth,
+                        ),
+                        ShaderDefVal::UInt(
+                            "SURVIVAL_MASK".into(),
+                            default_rules.survival,
+                        ),
+                    ],
                     entry_point: Cow::from("init"),
                 },
             );
-        let update_pipeline = pipeline_cache
-            .queue_compute_pipeline(
-                ComputePipelineDescriptor {
-                    label: None,
-                    layout: Some(vec![
-                        texture_bind_group_layout.clone(),
-                    ]),
-                    shader,
-                    shader_defs: vec![],
-                    entry_point: Cow::from("update"),
-                },
-            );
 
         GameOfLifePipeline {
             texture_bind_group_layout,
+            shader,
             init_pipeline,
-            update_pipeline,
         }
     }
 }
 
+impl SpecializedComputePipeline for GameOfLifePipeline {
+    type Key = GameOfLifeRules;
+
+    fn specialize(&self, rules: Self::Key) -> ComputePipelineDescriptor {
+        ComputePipelineDescriptor {
+            label: None,
+            layout: Some(vec![self.texture_bind_group_layout.clone()]),
+            shader: self.shader.clone(),
+            shader_defs: vec![
+                ShaderDefVal::UInt("BIRTH_MASK".into(), rules.birth),
+                ShaderDefVal::UInt("SURVIVAL_MASK".into(), rules.survival),
+            ],
+            entry_point: Cow::from("update"),
+        }
+    }
+}
+
+/// The compute pipeline variant specialized for the current [`GameOfLifeRules`].
+/// Re-queued whenever the rules change so [`GameOfLifeNode`] picks up the new
+/// variant without recompiling every frame.
+#[derive(Resource)]
+struct GameOfLifeUpdatePipelineId(CachedComputePipelineId);
+
+fn queue_pipeline(
+    mut commands: Commands,
+    pipeline: Res<GameOfLifePipeline>,
+    mut specialized_pipelines: ResMut<
+        SpecializedComputePipelines<GameOfLifePipeline>,
+    >,
+    mut pipeline_cache: ResMut<PipelineCache>,
+    rules: Res<GameOfLifeRules>,
+) {
+    let id = specialized_pipelines.specialize(
+        &mut pipeline_cache,
+        &pipeline,
+        *rules,
+    );
+    commands.insert_resource(GameOfLifeUpdatePipelineId(id));
+}
+
 enum GameOfLifeState {
     Loading,
     Init,
@@ -278,13 +594,19 @@ impl render_graph::Node for GameOfLifeNode {
                 }
             }
             GameOfLifeState::Init => {
-                if let CachedPipelineState::Ok(_) =
-                    pipeline_cache
-                        .get_compute_pipeline_state(
-                            pipeline.update_pipeline,
-                        )
+                // The specialized update variant is only available once
+                // `queue_pipeline` has run at least once.
+                if let Some(update_pipeline_id) =
+                    world.get_resource::<GameOfLifeUpdatePipelineId>()
                 {
-                    self.state = GameOfLifeState::Update;
+                    if let CachedPipelineState::Ok(_) =
+                        pipeline_cache
+                            .get_compute_pipeline_state(
+                                update_pipeline_id.0,
+                            )
+                    {
+                        self.state = GameOfLifeState::Update;
+                    }
                 }
             }
             GameOfLifeState::Update => {}
@@ -297,12 +619,33 @@ impl render_graph::Node for GameOfLifeNode {
         render_context: &mut RenderContext,
         world: &World,
     ) -> Result<(), render_graph::NodeRunError> {
-        let texture_bind_group =
-            &world.resource::<GameOfLifeImageBindGroup>().0;
+        let bind_groups =
+            world.resource::<GameOfLifeImageBindGroups>();
         let pipeline_cache =
             world.resource::<PipelineCache>();
         let pipeline =
             world.resource::<GameOfLifePipeline>();
+        let config = world.resource::<GameOfLifeConfig>();
+        let workgroups_x =
+            ceil_div(config.width, WORKGROUP_SIZE);
+        let workgroups_y =
+            ceil_div(config.height, WORKGROUP_SIZE);
+
+        // `GameOfLifeFrontBuffer` is the single source of truth for parity,
+        // shared directly with the main world so its sprite can display
+        // whichever texture this node last wrote to.
+        let frame_parity = world
+            .resource::<GameOfLifeFrontBuffer>()
+            .0
+            .load(Ordering::Relaxed);
+
+        // `texture_a` is the input while parity is `false`, so the output
+        // (the new front buffer) is always the other texture.
+        let bind_group = if frame_parity {
+            &bind_groups.b_to_a
+        } else {
+            &bind_groups.a_to_b
+        };
 
         let mut pass = render_context
             .command_encoder
@@ -310,7 +653,8 @@ impl render_graph::Node for GameOfLifeNode {
                 &ComputePassDescriptor::default(),
             );
 
-        pass.set_bind_group(0, texture_bind_group, &[]);
+        pass.set_bind_group(0, bind_group, &[]);
+        let mut update_skipped = false;
 
         // select the pipeline based on the current state
         match self.state {
@@ -323,26 +667,101 @@ impl render_graph::Node for GameOfLifeNode {
                     .unwrap();
                 pass.set_pipeline(init_pipeline);
                 pass.dispatch_workgroups(
-                    SIZE.0 / WORKGROUP_SIZE,
-                    SIZE.1 / WORKGROUP_SIZE,
+                    workgroups_x,
+                    workgroups_y,
                     1,
                 );
             }
             GameOfLifeState::Update => {
-                let update_pipeline = pipeline_cache
-                    .get_compute_pipeline(
-                        pipeline.update_pipeline,
-                    )
-                    .unwrap();
-                pass.set_pipeline(update_pipeline);
-                pass.dispatch_workgroups(
-                    SIZE.0 / WORKGROUP_SIZE,
-                    SIZE.1 / WORKGROUP_SIZE,
-                    1,
-                );
+                let update_pipeline_id = world
+                    .resource::<GameOfLifeUpdatePipelineId>()
+                    .0;
+                // A rule switch re-specializes the pipeline the same frame
+                // it's queued; the new variant may not have finished
+                // compiling yet, so skip this dispatch rather than panic and
+                // just show the previous generation for one more frame.
+                if let Some(update_pipeline) = pipeline_cache
+                    .get_compute_pipeline(update_pipeline_id)
+                {
+                    pass.set_pipeline(update_pipeline);
+                    pass.dispatch_workgroups(
+                        workgroups_x,
+                        workgroups_y,
+                        1,
+                    );
+                } else {
+                    update_skipped = true;
+                }
             }
         }
 
+        drop(pass);
+
+        // Flip parity so next frame reads from what was just written; a
+        // skipped update wrote nothing, so the front buffer stays as it is.
+        let new_parity = if update_skipped {
+            frame_parity
+        } else {
+            !frame_parity
+        };
+        world
+            .resource::<GameOfLifeFrontBuffer>()
+            .0
+            .store(new_parity, Ordering::Relaxed);
+
+        if matches!(self.state, GameOfLifeState::Update)
+            && world
+                .resource::<GameOfLifeReadbackRequest>()
+                .0
+        {
+            let images = world.resource::<GameOfLifeImages>();
+            let front_handle = if new_parity {
+                &images.texture_b
+            } else {
+                &images.texture_a
+            };
+            let gpu_images =
+                world.resource::<RenderAssets<Image>>();
+            let front_image = &gpu_images[front_handle];
+            let render_device =
+                world.resource::<RenderDevice>();
+
+            let padded_row = padded_bytes_per_row(config.width);
+            let readback_buffer = render_device
+                .create_buffer(&BufferDescriptor {
+                    label: Some(
+                        "game of life readback buffer",
+                    ),
+                    size: (padded_row * config.height) as u64,
+                    usage: BufferUsages::COPY_DST
+                        | BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                });
+
+            render_context.command_encoder.copy_texture_to_buffer(
+                front_image.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &readback_buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: std::num::NonZeroU32::new(padded_row),
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: config.width,
+                    height: config.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            *world
+                .resource::<GameOfLifePendingReadback>()
+                .0
+                .lock()
+                .unwrap() = Some(readback_buffer);
+        }
+
         Ok(())
     }
 }
@@ -382,3 +801,72 @@ fn prepare_time(
         ]),
     );
 }
+
+/// Backs the shader's `dimensions` uniform, used for the edge bounds-check.
+#[derive(Resource)]
+struct DimensionsMeta {
+    buffer: Buffer,
+}
+
+// write the extracted grid size into the dimensions uniform buffer
+fn prepare_dimensions(
+    config: Res<GameOfLifeConfig>,
+    dimensions_meta: ResMut<DimensionsMeta>,
+    render_queue: Res<RenderQueue>,
+) {
+    render_queue.write_buffer(
+        &dimensions_meta.buffer,
+        0,
+        bevy::core::cast_slice(&[
+            config.width,
+            config.height,
+        ]),
+    );
+}
+
+/// The render-world end of the readback channel; [`map_and_send_readback`]
+/// forwards decoded snapshots to the main world's
+/// [`GameOfLifeReadbackReceiver`] through this.
+#[derive(Resource)]
+struct GameOfLifeReadbackSender(Sender<Vec<u8>>);
+
+/// Holds the buffer [`GameOfLifeNode::run`] just issued a
+/// `copy_texture_to_buffer` into, until [`map_and_send_readback`] maps it and
+/// ships the decoded bytes off. `Node::run` only has `&World` access, so a
+/// `Mutex` stands in for the `&mut` it can't take.
+#[derive(Resource, Default)]
+struct GameOfLifePendingReadback(std::sync::Mutex<Option<Buffer>>);
+
+/// Maps any buffer queued by `GameOfLifeNode::run` this frame, strips the
+/// wgpu row padding back out, and forwards the resulting RGBA8 bytes to the
+/// main world.
+fn map_and_send_readback(
+    pending_readback: Res<GameOfLifePendingReadback>,
+    readback_sender: Res<GameOfLifeReadbackSender>,
+    render_device: Res<RenderDevice>,
+    config: Res<GameOfLifeConfig>,
+) {
+    let buffer = match pending_readback.0.lock().unwrap().take()
+    {
+        Some(buffer) => buffer,
+        None => return,
+    };
+
+    let buffer_slice = buffer.slice(..);
+    buffer_slice.map_async(MapMode::Read, |_| {});
+    render_device.wgpu_device().poll(Maintain::Wait);
+
+    let padded_row = padded_bytes_per_row(config.width) as usize;
+    let unpadded_row = (config.width * 4) as usize;
+    let mapped_range = buffer_slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity(
+        unpadded_row * config.height as usize,
+    );
+    for row in mapped_range.chunks(padded_row) {
+        pixels.extend_from_slice(&row[..unpadded_row]);
+    }
+    drop(mapped_range);
+    buffer.unmap();
+
+    let _ = readback_sender.0.send(pixels);
+}
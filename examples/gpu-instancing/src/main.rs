@@ -0,0 +1,351 @@
+//! A GPU instancing example: renders thousands of copies of a single mesh in
+//! one draw call using a custom render pipeline and a per-instance vertex
+//! buffer (position offset + scale + color per instance).
+
+use bevy::{
+    core_pipeline::core_3d::Transparent3d,
+    ecs::system::{
+        lifetimeless::*, SystemParamItem,
+    },
+    pbr::{
+        MeshPipeline, MeshPipelineKey, SetMeshBindGroup,
+        SetMeshViewBindGroup,
+    },
+    prelude::*,
+    render::{
+        extract_component::{
+            ExtractComponent, ExtractComponentPlugin,
+        },
+        mesh::{GpuBufferInfo, MeshVertexBufferLayout},
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem,
+            RenderCommand, RenderCommandResult,
+            RenderPhase, SetItemPipeline,
+            TrackedRenderPass,
+        },
+        render_resource::*,
+        renderer::RenderDevice,
+        view::{ExtractedView, NoFrustumCulling},
+        RenderApp, RenderStage,
+    },
+};
+use bytemuck::{Pod, Zeroable};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(CustomMaterialPlugin)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    commands.spawn((
+        meshes.add(Mesh::from(shape::Cube { size: 0.5 })),
+        SpatialBundle::default(),
+        InstanceMaterialData(
+            (1..=10)
+                .flat_map(|x| (1..=10).map(move |y| (x, y)))
+                .flat_map(|(x, y)| {
+                    (1..=10).map(move |z| (x, y, z))
+                })
+                .map(|(x, y, z)| InstanceData {
+                    position: Vec3::new(
+                        x as f32 - 5.0,
+                        y as f32 - 5.0,
+                        z as f32 - 5.0,
+                    ),
+                    scale: 0.3,
+                    color: Color::hsla(
+                        x as f32 * 36.0,
+                        y as f32 / 10.0,
+                        z as f32 / 20.0,
+                        1.0,
+                    )
+                    .as_rgba_f32(),
+                })
+                .collect(),
+        ),
+        // NoFrustumCulling is needed since the instance bounds are not known
+        // to Bevy's default frustum culling, which only inspects the mesh.
+        NoFrustumCulling,
+    ));
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 15.0, 15.0)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+#[derive(Component, Deref, Clone)]
+struct InstanceMaterialData(Vec<InstanceData>);
+
+impl ExtractComponent for InstanceMaterialData {
+    type Query = &'static InstanceMaterialData;
+    type Filter = ();
+    type Out = Self;
+
+    fn extract_component(
+        item: bevy::ecs::query::QueryItem<'_, Self::Query>,
+    ) -> Option<Self> {
+        Some(item.clone())
+    }
+}
+
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct InstanceData {
+    position: Vec3,
+    scale: f32,
+    color: [f32; 4],
+}
+
+struct CustomMaterialPlugin;
+
+impl Plugin for CustomMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(
+            ExtractComponentPlugin::<InstanceMaterialData>::default(
+            ),
+        );
+        app.sub_app_mut(RenderApp)
+            .add_render_command::<Transparent3d, DrawCustom>()
+            .init_resource::<CustomPipeline>()
+            .init_resource::<SpecializedMeshPipelines<CustomPipeline>>()
+            .add_system_to_stage(RenderStage::Queue, queue_custom)
+            .add_system_to_stage(
+                RenderStage::Prepare,
+                prepare_instance_buffers,
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_custom(
+    transparent_3d_draw_functions: Res<
+        DrawFunctions<Transparent3d>,
+    >,
+    custom_pipeline: Res<CustomPipeline>,
+    msaa: Res<Msaa>,
+    mut pipelines: ResMut<
+        SpecializedMeshPipelines<CustomPipeline>,
+    >,
+    mut pipeline_cache: ResMut<PipelineCache>,
+    meshes: Res<RenderAssets<Mesh>>,
+    material_meshes: Query<
+        (Entity, &Handle<Mesh>, &Transform),
+        With<InstanceMaterialData>,
+    >,
+    mut views: Query<(
+        &ExtractedView,
+        &mut RenderPhase<Transparent3d>,
+    )>,
+) {
+    let draw_custom = transparent_3d_draw_functions
+        .read()
+        .get_id::<DrawCustom>()
+        .unwrap();
+
+    let msaa_key =
+        MeshPipelineKey::from_msaa_samples(msaa.samples);
+
+    for (view, mut transparent_phase) in &mut views {
+        let view_key = msaa_key
+            | MeshPipelineKey::from_hdr(view.hdr);
+        let rangefinder = view.rangefinder3d();
+        for (entity, mesh_handle, transform) in
+            &material_meshes
+        {
+            if let Some(mesh) = meshes.get(mesh_handle) {
+                let key = view_key
+                    | MeshPipelineKey::from_primitive_topology(
+                        mesh.primitive_topology,
+                    );
+                let pipeline = pipelines
+                    .specialize(
+                        &mut pipeline_cache,
+                        &custom_pipeline,
+                        key,
+                        &mesh.layout,
+                    )
+                    .unwrap();
+                transparent_phase.add(Transparent3d {
+                    entity,
+                    pipeline,
+                    draw_function: draw_custom,
+                    distance: rangefinder
+                        .distance(&transform.compute_matrix()),
+                });
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct InstanceBuffer {
+    buffer: Buffer,
+    length: usize,
+}
+
+fn prepare_instance_buffers(
+    mut commands: Commands,
+    query: Query<(Entity, &InstanceMaterialData)>,
+    render_device: Res<RenderDevice>,
+) {
+    for (entity, instance_data) in &query {
+        let buffer = render_device.create_buffer_with_data(
+            &BufferInitDescriptor {
+                label: Some("instance data buffer"),
+                contents: bytemuck::cast_slice(
+                    instance_data.as_slice(),
+                ),
+                usage: BufferUsages::VERTEX
+                    | BufferUsages::COPY_DST,
+            },
+        );
+        commands.entity(entity).insert(InstanceBuffer {
+            buffer,
+            length: instance_data.len(),
+        });
+    }
+}
+
+#[derive(Resource)]
+pub struct CustomPipeline {
+    shader: Handle<Shader>,
+    mesh_pipeline: MeshPipeline,
+}
+
+impl FromWorld for CustomPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let shader = world
+            .resource::<AssetServer>()
+            .load("shaders/instancing.wgsl");
+
+        let mesh_pipeline =
+            world.resource::<MeshPipeline>();
+
+        CustomPipeline {
+            shader,
+            mesh_pipeline: mesh_pipeline.clone(),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for CustomPipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<
+        RenderPipelineDescriptor,
+        SpecializedMeshPipelineError,
+    > {
+        let mut descriptor = self
+            .mesh_pipeline
+            .specialize(key, layout)?;
+        descriptor.vertex.shader = self.shader.clone();
+        descriptor.vertex.buffers.push(
+            VertexBufferLayout {
+                array_stride: std::mem::size_of::<
+                    InstanceData,
+                >() as u64,
+                step_mode: VertexStepMode::Instance,
+                attributes: vec![
+                    VertexAttribute {
+                        format: VertexFormat::Float32x4,
+                        offset: 0,
+                        shader_location: 3,
+                    },
+                    VertexAttribute {
+                        format: VertexFormat::Float32x4,
+                        offset: VertexFormat::Float32x4
+                            .size(),
+                        shader_location: 4,
+                    },
+                ],
+            },
+        );
+        descriptor.fragment.as_mut().unwrap().shader =
+            self.shader.clone();
+        Ok(descriptor)
+    }
+}
+
+type DrawCustom = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    DrawMeshInstanced,
+);
+
+struct DrawMeshInstanced;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawMeshInstanced {
+    type Param = SRes<RenderAssets<Mesh>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = (
+        Read<Handle<Mesh>>,
+        Read<InstanceBuffer>,
+    );
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        (mesh_handle, instance_buffer): (
+            &'w Handle<Mesh>,
+            &'w InstanceBuffer,
+        ),
+        meshes: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let gpu_mesh =
+            match meshes.into_inner().get(mesh_handle) {
+                Some(gpu_mesh) => gpu_mesh,
+                None => return RenderCommandResult::Failure,
+            };
+
+        pass.set_vertex_buffer(
+            0,
+            gpu_mesh.vertex_buffer.slice(..),
+        );
+        pass.set_vertex_buffer(
+            1,
+            instance_buffer.buffer.slice(..),
+        );
+
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(
+                    buffer.slice(..),
+                    0,
+                    *index_format,
+                );
+                pass.draw_indexed(
+                    0..*count,
+                    0,
+                    0..instance_buffer.length as u32,
+                );
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(
+                    0..*vertex_count,
+                    0..instance_buffer.length as u32,
+                );
+            }
+        }
+        RenderCommandResult::Success
+    }
+}